@@ -15,12 +15,53 @@ struct Footer {
 /// Alias for the name of a CLI option.
 type Name = String;
 
+/// Errors produced while parsing command-line arguments.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// An argument looked like an option but matched no registered short/long form.
+    UnrecognizedOption(String),
+    /// An option that requires a value was given without one.
+    MissingArgument(String),
+    /// An option that does not take a value was given one anyway.
+    UnexpectedValue(String),
+    /// One or more required options were never enabled.
+    MissingRequiredOption(Vec<String>),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::UnrecognizedOption(option) => {
+                write!(f, "unrecognized option: {option}")
+            }
+            ParseError::MissingArgument(option) => {
+                write!(f, "option {option} is missing its argument")
+            }
+            ParseError::UnexpectedValue(option) => {
+                write!(f, "option {option} does not take a value")
+            }
+            ParseError::MissingRequiredOption(names) => {
+                write!(f, "missing required option(s): {}", names.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
 /// Represents a CLI option with its short form, long form, help text, and name.
 #[derive(Debug)]
 struct CliOption {
     long_form: Option<String>,
     short_form: Option<String>,
     help_text: String,
+    /// Whether this option expects a value (e.g. `--count 10`) or is a plain flag.
+    takes_value: bool,
+    /// Whether `parse_from` must fail if this option is never enabled.
+    required: bool,
+    /// Environment variable consulted for a default value when the option is
+    /// absent from argv.
+    env_var: Option<String>,
 }
 
 /// Represents the value(s) associated with a CLI option and whether it is enabled.
@@ -51,6 +92,16 @@ pub struct CliOptionParser {
     name_to_option_value_map: HashMap<Name, OptionValue>,
     short_form_to_name_map: HashMap<String, Name>,
     long_form_to_name_map: HashMap<String, Name>,
+    name_to_subcommand_map: HashMap<Name, CliOptionParser>,
+    matched_subcommand: Option<Name>,
+    /// Whether this parser was registered as someone else's subcommand.
+    ///
+    /// A top-level parser's `args` start with a program name that isn't a
+    /// normal argument worth matching against subcommands, so it waits for
+    /// one normal argument before checking. A sub-parser's `args` (the
+    /// remainder after its own name was consumed by the parent) have no such
+    /// placeholder, so it must check starting from the very first one.
+    is_subcommand: bool,
     empty_option_list: Vec<String>,
     header: Header,
     footer: Footer,
@@ -73,6 +124,9 @@ impl CliOptionParser {
             name_to_option_value_map: HashMap::new(),
             short_form_to_name_map: HashMap::new(),
             long_form_to_name_map: HashMap::new(),
+            name_to_subcommand_map: HashMap::new(),
+            matched_subcommand: None,
+            is_subcommand: false,
             empty_option_list: vec![],
             header: Header { text: header },
             footer: Footer { text: footer },
@@ -106,12 +160,47 @@ impl CliOptionParser {
         option_value_entry.is_enabled = true;
     }
 
+    /// Returns how many normal arguments this parser expects to see before a
+    /// subcommand name could appear: one (the program name) for a top-level
+    /// parser, zero for a parser registered via `register_subcommand`, since
+    /// its `args` start right after the name that dispatched to it.
+    fn leading_normal_arguments_before_subcommand(&self) -> usize {
+        if self.is_subcommand {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Returns whether the option registered under `name` expects a value.
+    fn takes_value(&self, name: &str) -> bool {
+        self.name_to_cli_option_map
+            .get(name)
+            .map(|option| option.takes_value)
+            .unwrap_or(false)
+    }
+
     /// Parses the given arguments and returns the list of normal arguments.
-    fn parse_from(&mut self, args: Vec<String>) -> Vec<String> {
+    ///
+    /// Returns the first `ParseError` encountered (an unrecognized option, a
+    /// value-taking option that ran out of arguments, or a flag given a value
+    /// via `=`) rather than silently dropping the offending argument. Once all
+    /// arguments are consumed, fails with `ParseError::MissingRequiredOption`
+    /// naming every required option that is still missing.
+    fn parse_from(&mut self, args: Vec<String>) -> Result<Vec<String>, ParseError> {
         let mut arguments: Vec<String> = vec![];
-
-        for arg in args {
-            if arg.starts_with("--") {
+        let mut index = 0;
+
+        while index < args.len() {
+            let arg = args[index].clone();
+
+            if arg == "--" {
+                // End-of-options marker: every remaining argument, even ones
+                // starting with '-', is a normal argument from here on.
+                arguments.extend(args[(index + 1)..].iter().cloned());
+                index = args.len();
+                continue;
+            } else if arg.starts_with("--") {
                 if arg.contains("=") {
                     // Example: --hello=world
                     let mut arg_split = arg.split("=");
@@ -119,49 +208,163 @@ impl CliOptionParser {
                     let value = arg_split.next().unwrap(); // world
 
                     if !self.long_form_to_name_map.contains_key(option) {
-                        continue;
+                        return Err(ParseError::UnrecognizedOption(option.to_string()));
                     }
 
-                    let option_name = &self.long_form_to_name_map[option];
-                    self.add_value_to_option(option_name.to_string(), value.to_string());
+                    let option_name = self.long_form_to_name_map[option].clone();
+                    if !self.takes_value(&option_name) {
+                        return Err(ParseError::UnexpectedValue(option.to_string()));
+                    }
+                    self.add_value_to_option(option_name, value.to_string());
                 } else {
                     if !self.long_form_to_name_map.contains_key(&arg) {
-                        continue;
+                        return Err(ParseError::UnrecognizedOption(arg));
                     }
 
-                    let option_name = &self.long_form_to_name_map[&arg];
-                    self.enable_option(option_name.to_string());
+                    let option_name = self.long_form_to_name_map[&arg].clone();
+                    if self.takes_value(&option_name) {
+                        match args.get(index + 1) {
+                            Some(value) => {
+                                self.add_value_to_option(option_name, value.to_string());
+                                index += 1;
+                            }
+                            None => return Err(ParseError::MissingArgument(arg)),
+                        }
+                    } else {
+                        self.enable_option(option_name);
+                    }
                 }
-            } else if arg.starts_with("-") {
+            } else if let Some(short_flags) = arg.strip_prefix('-') {
                 if arg.len() > 1 {
-                    // Example: -lHelloWorld
-                    let (option, value) = arg.split_at(2); // left = -l, right = HelloWorld
-                    if !self.short_form_to_name_map.contains_key(option) {
-                        continue;
+                    // Example: -lHelloWorld, or clustered flags like -abc.
+                    // Validate the whole cluster before enabling/storing
+                    // anything, so a later unrecognized char in the same
+                    // token can't leave earlier flags in it half-applied.
+                    let chars: Vec<char> = short_flags.chars().collect();
+                    let mut char_index = 0;
+                    let mut flags_to_enable: Vec<String> = vec![];
+                    let mut value_to_add: Option<(String, String)> = None;
+                    let mut consumed_next_arg = false;
+
+                    while char_index < chars.len() {
+                        let short_form = format!("-{}", chars[char_index]);
+                        if !self.short_form_to_name_map.contains_key(&short_form) {
+                            return Err(ParseError::UnrecognizedOption(short_form));
+                        }
+
+                        let option_name = self.short_form_to_name_map[&short_form].clone();
+                        if self.takes_value(&option_name) {
+                            let glued_value: String = chars[(char_index + 1)..].iter().collect();
+                            if !glued_value.is_empty() {
+                                value_to_add = Some((option_name, glued_value));
+                            } else {
+                                match args.get(index + 1) {
+                                    Some(value) => {
+                                        value_to_add = Some((option_name, value.to_string()));
+                                        consumed_next_arg = true;
+                                    }
+                                    None => return Err(ParseError::MissingArgument(short_form)),
+                                }
+                            }
+                            break;
+                        } else {
+                            flags_to_enable.push(option_name);
+                            char_index += 1;
+                        }
+                    }
+
+                    for flag_name in flags_to_enable {
+                        self.enable_option(flag_name);
+                    }
+                    if let Some((option_name, value)) = value_to_add {
+                        self.add_value_to_option(option_name, value);
+                    }
+                    if consumed_next_arg {
+                        index += 1;
                     }
-                    let option_name = &self.short_form_to_name_map[option];
-                    self.add_value_to_option(option_name.to_string(), value.to_string());
                 } else {
                     if !self.short_form_to_name_map.contains_key(&arg) {
-                        continue;
+                        return Err(ParseError::UnrecognizedOption(arg));
                     }
 
-                    let option_name = &self.short_form_to_name_map[&arg];
-                    self.enable_option(option_name.to_string());
+                    let option_name = self.short_form_to_name_map[&arg].clone();
+                    if self.takes_value(&option_name) {
+                        match args.get(index + 1) {
+                            Some(value) => {
+                                self.add_value_to_option(option_name, value.to_string());
+                                index += 1;
+                            }
+                            None => return Err(ParseError::MissingArgument(arg)),
+                        }
+                    } else {
+                        self.enable_option(option_name);
+                    }
                 }
+            } else if arguments.len() == self.leading_normal_arguments_before_subcommand()
+                && self.name_to_subcommand_map.contains_key(&arg)
+            {
+                // The first normal argument belonging to this parser (after
+                // the program-name placeholder, for a top-level parser)
+                // matches a registered subcommand: route everything after it
+                // there.
+                let remaining_args = args[(index + 1)..].to_vec();
+                let subcommand_parser = self.name_to_subcommand_map.get_mut(&arg).unwrap();
+                let subcommand_arguments = subcommand_parser.parse_from(remaining_args)?;
+
+                self.matched_subcommand = Some(arg);
+                arguments.extend(subcommand_arguments);
+
+                self.apply_env_defaults();
+                let missing = self.missing_required_options();
+                if !missing.is_empty() {
+                    return Err(ParseError::MissingRequiredOption(missing));
+                }
+
+                return Ok(arguments);
             } else {
                 arguments.push(arg);
             }
+
+            index += 1;
         }
 
-        arguments
+        self.apply_env_defaults();
+        let missing = self.missing_required_options();
+        if !missing.is_empty() {
+            return Err(ParseError::MissingRequiredOption(missing));
+        }
+
+        Ok(arguments)
     }
 
     /// Parses the command-line arguments and returns the list of normal arguments.
-    pub fn parse(&mut self) -> Vec<String> {
+    pub fn parse(&mut self) -> Result<Vec<String>, ParseError> {
         self.parse_from(std::env::args().collect())
     }
 
+    /// Registers `parser` as a subcommand, dispatched when the first normal
+    /// argument equals `name` (e.g. `git commit` dispatches the `commit` subcommand).
+    pub fn register_subcommand(&mut self, name: &str, mut parser: CliOptionParser) {
+        let name = name.to_string();
+
+        if self.name_to_subcommand_map.contains_key(&name) {
+            panic!("subcommand : {name} already registered");
+        }
+
+        parser.is_subcommand = true;
+        self.name_to_subcommand_map.insert(name, parser);
+    }
+
+    /// Returns the name of the subcommand matched during parsing, if any.
+    pub fn matched_subcommand(&self) -> Option<&str> {
+        self.matched_subcommand.as_deref()
+    }
+
+    /// Returns the sub-parser registered under `name`, for inspecting its parsed options.
+    pub fn subcommand(&self, name: &str) -> Option<&CliOptionParser> {
+        self.name_to_subcommand_map.get(name)
+    }
+
     /// Checks if the option with the given name is enabled.
     pub fn is_enabled(&self, name: &str) -> bool {
         if !self.name_to_option_value_map.contains_key(name) {
@@ -180,13 +383,36 @@ impl CliOptionParser {
         &self.name_to_option_value_map[name].values()
     }
 
+    /// Parses and returns the last value stored for `name`, converted via `T::from_str`.
+    ///
+    /// Returns `Ok(None)` if the option was never enabled, and `Err` if the stored
+    /// value could not be parsed as `T`.
+    pub fn get<T: std::str::FromStr>(&self, name: &str) -> Result<Option<T>, T::Err> {
+        match self.get_option_values(name).last() {
+            Some(value) => value.parse::<T>().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses and returns every value stored for `name`, converted via `T::from_str`.
+    pub fn get_all<T: std::str::FromStr>(&self, name: &str) -> Result<Vec<T>, T::Err> {
+        self.get_option_values(name)
+            .iter()
+            .map(|value| value.parse::<T>())
+            .collect()
+    }
+
     /// Registers a new CLI option with the given short form, long form, help text, and name.
+    ///
+    /// `takes_value` controls whether the option expects an accompanying value
+    /// (`--count 10`) or is a plain on/off flag (`--verbose`).
     pub fn register_option(
         &mut self,
         short_form: Option<String>,
         long_form: Option<String>,
         help_text: &str,
         name: &str,
+        takes_value: bool,
     ) {
         let help_text = help_text.to_string();
         let name = name.to_string();
@@ -229,6 +455,9 @@ impl CliOptionParser {
                 long_form,
                 short_form,
                 help_text,
+                takes_value,
+                required: false,
+                env_var: None,
             },
         );
 
@@ -241,6 +470,72 @@ impl CliOptionParser {
         );
     }
 
+    /// Registers a required CLI option: `parse_from` fails with
+    /// `ParseError::MissingRequiredOption` if it is never enabled.
+    pub fn register_required_option(
+        &mut self,
+        short_form: Option<String>,
+        long_form: Option<String>,
+        help_text: &str,
+        name: &str,
+        takes_value: bool,
+    ) {
+        self.register_option(short_form, long_form, help_text, name, takes_value);
+        self.name_to_cli_option_map.get_mut(name).unwrap().required = true;
+    }
+
+    /// Registers a CLI option that falls back to the environment variable
+    /// `env_var` when absent from argv. CLI arguments always take precedence.
+    pub fn register_option_with_env(
+        &mut self,
+        short_form: Option<String>,
+        long_form: Option<String>,
+        help_text: &str,
+        name: &str,
+        takes_value: bool,
+        env_var: &str,
+    ) {
+        self.register_option(short_form, long_form, help_text, name, takes_value);
+        self.name_to_cli_option_map.get_mut(name).unwrap().env_var = Some(env_var.to_string());
+    }
+
+    /// Enables any registered option that has an associated environment
+    /// variable, is not already enabled, and whose variable is present in
+    /// the environment. CLI arguments always take precedence.
+    fn apply_env_defaults(&mut self) {
+        let env_defaults: Vec<(String, String)> = self
+            .name_to_cli_option_map
+            .iter()
+            .filter(|(name, _)| !self.is_enabled(name))
+            .filter_map(|(name, option)| {
+                option
+                    .env_var
+                    .as_ref()
+                    .and_then(|env_var| std::env::var(env_var).ok())
+                    .map(|value| (name.clone(), value))
+            })
+            .collect();
+
+        for (name, value) in env_defaults {
+            self.add_value_to_option(name, value);
+        }
+    }
+
+    /// Returns the names of all required options that are not enabled (or, for
+    /// a value-taking option, have no value), or empty if every required
+    /// option was satisfied.
+    fn missing_required_options(&self) -> Vec<String> {
+        self.name_to_cli_option_map
+            .iter()
+            .filter(|(_, option)| option.required)
+            .filter(|(name, option)| {
+                !self.is_enabled(name)
+                    || (option.takes_value && self.get_option_values(name).is_empty())
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
     /// Generates and returns the help text for the CLI options.
     pub fn help_text(&self) -> String {
         let mut help_text = format!("{}\n\n", self.header.text);
@@ -256,7 +551,20 @@ impl CliOptionParser {
                 None => help_text += &format!("     "),
             }
 
-            help_text += &format!("     {}\n", cli_option.help_text.replace('\n', "\n\t\t\t"));
+            help_text += &format!("     {}", cli_option.help_text.replace('\n', "\n\t\t\t"));
+
+            if let Some(env_var) = cli_option.env_var.as_ref() {
+                help_text += &format!(" [env: {env_var}]");
+            }
+
+            help_text += "\n";
+        }
+
+        if !self.name_to_subcommand_map.is_empty() {
+            help_text += "\nSubcommands:\n";
+            for name in self.name_to_subcommand_map.keys() {
+                help_text += &format!("    {name}\n");
+            }
         }
 
         help_text += "\n";
@@ -279,6 +587,7 @@ mod tests {
             Some("--count".to_string()),
             "print only count of selected lines",
             "count",
+            true,
         );
 
         cli_option_parser.register_option(
@@ -286,6 +595,7 @@ mod tests {
             Some("--context".to_string()),
             "print NUM lines of output contex when\n given with --context=NUM",
             "context",
+            true,
         );
 
         let mock_args = vec![
@@ -296,7 +606,7 @@ mod tests {
             "--context=712".to_string(),
         ];
 
-        let arguments = cli_option_parser.parse_from(mock_args);
+        let arguments = cli_option_parser.parse_from(mock_args).unwrap();
 
         assert_eq!(arguments, vec!["program_name"]);
 
@@ -316,4 +626,350 @@ mod tests {
         assert!(help_text.contains("-c    --count         print only count of selected lines"));
         assert!(help_text.contains("-C    --context         print NUM lines of output contex when\n\t\t\t given with --context=NUM"));
     }
+
+    #[test]
+    fn reports_unrecognized_option() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(
+            Some("-c".to_string()),
+            Some("--count".to_string()),
+            "print only count of selected lines",
+            "count",
+            true,
+        );
+
+        let mock_args = vec!["program_name".to_string(), "--unknown".to_string()];
+
+        assert_eq!(
+            cli_option_parser.parse_from(mock_args),
+            Err(ParseError::UnrecognizedOption("--unknown".to_string()))
+        );
+    }
+
+    #[test]
+    fn reports_unexpected_value_for_flag_given_via_equals() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(
+            None,
+            Some("--verbose".to_string()),
+            "print verbose output",
+            "verbose",
+            false,
+        );
+
+        let mock_args = vec!["program_name".to_string(), "--verbose=true".to_string()];
+
+        assert_eq!(
+            cli_option_parser.parse_from(mock_args),
+            Err(ParseError::UnexpectedValue("--verbose".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_space_separated_values() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(
+            Some("-c".to_string()),
+            Some("--count".to_string()),
+            "print only count of selected lines",
+            "count",
+            true,
+        );
+        cli_option_parser.register_option(
+            Some("-v".to_string()),
+            Some("--verbose".to_string()),
+            "print verbose output",
+            "verbose",
+            false,
+        );
+
+        let mock_args = vec![
+            "program_name".to_string(),
+            "--count".to_string(),
+            "10".to_string(),
+            "-v".to_string(),
+            "file.txt".to_string(),
+        ];
+
+        let arguments = cli_option_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(arguments, vec!["program_name", "file.txt"]);
+        assert_eq!(cli_option_parser["count"], vec!["10"]);
+        assert!(cli_option_parser.is_enabled("verbose"));
+        assert!(cli_option_parser["verbose"].is_empty());
+    }
+
+    #[test]
+    fn reports_missing_argument_for_value_option_at_end_of_args() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(
+            Some("-c".to_string()),
+            Some("--count".to_string()),
+            "print only count of selected lines",
+            "count",
+            true,
+        );
+
+        let mock_args = vec!["program_name".to_string(), "--count".to_string()];
+
+        assert_eq!(
+            cli_option_parser.parse_from(mock_args),
+            Err(ParseError::MissingArgument("--count".to_string()))
+        );
+    }
+
+    #[test]
+    fn typed_accessors_parse_stored_values() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(
+            Some("-c".to_string()),
+            Some("--count".to_string()),
+            "print only count of selected lines",
+            "count",
+            true,
+        );
+
+        let mock_args = vec![
+            "program_name".to_string(),
+            "--count=1".to_string(),
+            "--count=2".to_string(),
+        ];
+        cli_option_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(cli_option_parser.get::<u32>("count"), Ok(Some(2)));
+        assert_eq!(cli_option_parser.get_all::<u32>("count"), Ok(vec![1, 2]));
+        assert_eq!(cli_option_parser.get::<u32>("missing"), Ok(None));
+    }
+
+    #[test]
+    fn dispatches_to_matched_subcommand() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+
+        let mut commit_parser =
+            CliOptionParser::new("commit header".to_string(), "commit footer".to_string());
+        commit_parser.register_option(
+            None,
+            Some("--message".to_string()),
+            "commit message",
+            "message",
+            true,
+        );
+        cli_option_parser.register_subcommand("commit", commit_parser);
+
+        let mock_args = vec![
+            "program_name".to_string(),
+            "commit".to_string(),
+            "--message=hello".to_string(),
+        ];
+
+        let arguments = cli_option_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(arguments, vec!["program_name"]);
+        assert_eq!(cli_option_parser.matched_subcommand(), Some("commit"));
+        assert_eq!(
+            cli_option_parser.subcommand("commit").unwrap()["message"],
+            vec!["hello"]
+        );
+    }
+
+    #[test]
+    fn dispatches_through_nested_subcommands() {
+        let mut add_parser =
+            CliOptionParser::new("add header".to_string(), "add footer".to_string());
+        add_parser.register_option(None, Some("--url".to_string()), "remote url", "url", true);
+
+        let mut remote_parser =
+            CliOptionParser::new("remote header".to_string(), "remote footer".to_string());
+        remote_parser.register_subcommand("add", add_parser);
+
+        let mut top_parser =
+            CliOptionParser::new("top header".to_string(), "top footer".to_string());
+        top_parser.register_subcommand("remote", remote_parser);
+
+        let mock_args = vec![
+            "program_name".to_string(),
+            "remote".to_string(),
+            "add".to_string(),
+            "--url=x".to_string(),
+        ];
+
+        let arguments = top_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(arguments, vec!["program_name"]);
+        assert_eq!(top_parser.matched_subcommand(), Some("remote"));
+
+        let remote = top_parser.subcommand("remote").unwrap();
+        assert_eq!(remote.matched_subcommand(), Some("add"));
+        assert_eq!(remote.subcommand("add").unwrap()["url"], vec!["x"]);
+    }
+
+    #[test]
+    fn reports_missing_required_option() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_required_option(
+            Some("-o".to_string()),
+            Some("--output".to_string()),
+            "output path",
+            "output",
+            true,
+        );
+
+        let mock_args = vec!["program_name".to_string()];
+
+        assert_eq!(
+            cli_option_parser.parse_from(mock_args),
+            Err(ParseError::MissingRequiredOption(vec!["output".to_string()]))
+        );
+    }
+
+    #[test]
+    fn accepts_required_option_when_present() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_required_option(
+            Some("-o".to_string()),
+            Some("--output".to_string()),
+            "output path",
+            "output",
+            true,
+        );
+
+        let mock_args = vec!["program_name".to_string(), "--output=out.txt".to_string()];
+
+        let arguments = cli_option_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(arguments, vec!["program_name"]);
+        assert_eq!(cli_option_parser["output"], vec!["out.txt"]);
+    }
+
+    #[test]
+    fn falls_back_to_env_var_when_option_absent() {
+        std::env::set_var("CLI_OPTION_PARSER_TEST_CONFIG", "from_env");
+
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option_with_env(
+            None,
+            Some("--config".to_string()),
+            "config file path",
+            "config",
+            true,
+            "CLI_OPTION_PARSER_TEST_CONFIG",
+        );
+
+        let arguments = cli_option_parser
+            .parse_from(vec!["program_name".to_string()])
+            .unwrap();
+
+        assert_eq!(arguments, vec!["program_name"]);
+        assert_eq!(cli_option_parser["config"], vec!["from_env"]);
+        assert!(cli_option_parser.help_text().contains("[env: CLI_OPTION_PARSER_TEST_CONFIG]"));
+
+        std::env::remove_var("CLI_OPTION_PARSER_TEST_CONFIG");
+    }
+
+    #[test]
+    fn cli_argument_takes_precedence_over_env_var() {
+        std::env::set_var("CLI_OPTION_PARSER_TEST_CONFIG2", "from_env");
+
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option_with_env(
+            None,
+            Some("--config".to_string()),
+            "config file path",
+            "config",
+            true,
+            "CLI_OPTION_PARSER_TEST_CONFIG2",
+        );
+
+        let mock_args = vec!["program_name".to_string(), "--config=from_cli".to_string()];
+        cli_option_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(cli_option_parser["config"], vec!["from_cli"]);
+
+        std::env::remove_var("CLI_OPTION_PARSER_TEST_CONFIG2");
+    }
+
+    #[test]
+    fn expands_clustered_short_flags() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(
+            Some("-a".to_string()),
+            None,
+            "flag a",
+            "a",
+            false,
+        );
+        cli_option_parser.register_option(
+            Some("-b".to_string()),
+            None,
+            "flag b",
+            "b",
+            false,
+        );
+        cli_option_parser.register_option(
+            Some("-c".to_string()),
+            None,
+            "flag c, takes a value",
+            "c",
+            true,
+        );
+
+        let mock_args = vec!["program_name".to_string(), "-abc123".to_string()];
+        let arguments = cli_option_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(arguments, vec!["program_name"]);
+        assert!(cli_option_parser.is_enabled("a"));
+        assert!(cli_option_parser.is_enabled("b"));
+        assert_eq!(cli_option_parser["c"], vec!["123"]);
+    }
+
+    #[test]
+    fn clustered_short_flags_leave_no_partial_state_on_error() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(Some("-a".to_string()), None, "flag a", "a", false);
+
+        let mock_args = vec!["program_name".to_string(), "-az".to_string()];
+
+        assert_eq!(
+            cli_option_parser.parse_from(mock_args),
+            Err(ParseError::UnrecognizedOption("-z".to_string()))
+        );
+        assert!(!cli_option_parser.is_enabled("a"));
+    }
+
+    #[test]
+    fn honors_end_of_options_terminator() {
+        let mut cli_option_parser =
+            CliOptionParser::new("header".to_string(), "footer".to_string());
+        cli_option_parser.register_option(
+            Some("-v".to_string()),
+            Some("--verbose".to_string()),
+            "print verbose output",
+            "verbose",
+            false,
+        );
+
+        let mock_args = vec![
+            "program_name".to_string(),
+            "--".to_string(),
+            "-v".to_string(),
+            "--verbose".to_string(),
+        ];
+
+        let arguments = cli_option_parser.parse_from(mock_args).unwrap();
+
+        assert_eq!(arguments, vec!["program_name", "-v", "--verbose"]);
+        assert!(!cli_option_parser.is_enabled("verbose"));
+    }
 }